@@ -8,17 +8,27 @@
 
 //! Device Manager for Legacy Devices.
 
+#[cfg(target_arch = "x86_64")]
+use std::collections::VecDeque;
+use std::fs::File;
 use std::io;
+use std::io::{Read, Write};
+use std::os::unix::io::{AsRawFd, RawFd};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
+use std::thread;
 
 use dbs_device::device_manager::Error as IoManagerError;
-use dbs_legacy_devices::SerialDevice;
-#[cfg(target_arch = "aarch64")]
-use dbs_legacy_devices::RTCDevice;
 use vmm_sys_util::eventfd::EventFd;
 
 // The I8042 Data Port (IO Port 0x60) is used for reading data that was received from a I8042 device or from the I8042 controller itself and writing data to a I8042 device or to the I8042 controller itself.
 const I8042_DATA_PORT: u16 = 0x60;
+// The I8042 command/status port, 4 bytes above the data port in the registered PIO range.
+const I8042_COMMAND_PORT_OFFSET: u64 = 0x4;
+
+// Size of the scratch buffer used to pump bytes between a serial backend and the guest.
+const SERIAL_PUMP_BUF_SIZE: usize = 512;
 
 /// Errors generated by legacy device manager.
 #[derive(Debug, thiserror::Error)]
@@ -34,36 +44,862 @@ pub enum Error {
     /// Failed to register/deregister interrupt.
     #[error("failure while managing interrupt for legacy device")]
     IrqManager(#[source] vmm_sys_util::errno::Error),
+
+    /// Failure while setting up a serial console backend.
+    #[error("failure while setting up serial console backend")]
+    SerialManager(#[source] io::Error),
+
+    /// Two or more legacy devices were configured to use overlapping PIO/MMIO ranges.
+    #[error("legacy device at {0:#x} overlaps with a range already in use")]
+    ResourceOverlap(u64),
+
+    /// Two or more legacy devices were configured to use the same IRQ line.
+    #[error("legacy device IRQ {0} is already in use")]
+    IrqOverlap(u32),
+
+    /// A required resource (PIO/MMIO range or IRQ) was not found for a legacy device.
+    #[error("no resources configured for legacy device '{0}'")]
+    MissingResource(String),
+
+    /// The requested legacy device was not instantiated by the `LegacyDeviceManagerBuilder`.
+    #[error("legacy device '{0}' was not instantiated")]
+    DeviceNotPresent(&'static str),
+}
+
+/// Describes where the host end of a guest serial port should be connected.
+#[derive(Debug, Clone)]
+pub enum ConsoleOutputMode {
+    /// The serial port is not connected to anything on the host.
+    Off,
+    /// The serial port is connected to the Dragonball process's own stdio.
+    Tty,
+    /// The serial port output is appended to a file at the given path.
+    File(PathBuf),
+    /// The serial port is exposed through a Unix domain socket at the given path.
+    /// A single client may connect and exchange bytes with the guest console.
+    Socket(PathBuf),
+    /// The serial port is connected to a freshly allocated pseudo-terminal.
+    /// The path of the PTY slave is made available through [`SerialManager::pty_slave_path`].
+    Pty,
+}
+
+/// `SerialManager` pumps bytes between a host backend (tty, file, unix socket or pty) and a
+/// guest [`SerialDevice`], running the transfer loop on a dedicated epoll-driven thread.
+///
+/// It is the host-facing counterpart of [`LegacyDeviceManager::get_com1_serial`] /
+/// [`LegacyDeviceManager::get_com2_serial`]: the manager only exposes the raw serial device,
+/// while `SerialManager` is what actually connects that device to something a user can interact
+/// with.
+pub struct SerialManager {
+    serial: Arc<Mutex<SerialDevice>>,
+    mode: ConsoleOutputMode,
+    epoll_fd: RawFd,
+    kill_evt: EventFd,
+    // Kept alive for as long as the manager lives; dropping it tears down the backend.
+    backend: SerialBackend,
+}
+
+enum SerialBackend {
+    Off,
+    Tty,
+    File(File),
+    Socket {
+        listener: UnixListener,
+        stream: Option<UnixStream>,
+    },
+    Pty {
+        master: File,
+        slave_path: PathBuf,
+    },
+}
+
+impl SerialBackend {
+    fn raw_fd(&self) -> Option<RawFd> {
+        match self {
+            SerialBackend::Off => None,
+            SerialBackend::Tty => Some(io::stdin().as_raw_fd()),
+            SerialBackend::File(_) => None,
+            SerialBackend::Socket { listener, stream } => Some(
+                stream
+                    .as_ref()
+                    .map(|s| s.as_raw_fd())
+                    .unwrap_or_else(|| listener.as_raw_fd()),
+            ),
+            SerialBackend::Pty { master, .. } => Some(master.as_raw_fd()),
+        }
+    }
+}
+
+impl SerialManager {
+    /// Create a new `SerialManager` that connects `serial` to the backend described by `mode`.
+    pub fn new(serial: Arc<Mutex<SerialDevice>>, mode: ConsoleOutputMode) -> Result<Self, Error> {
+        let backend = match &mode {
+            ConsoleOutputMode::Off => SerialBackend::Off,
+            ConsoleOutputMode::Tty => SerialBackend::Tty,
+            ConsoleOutputMode::File(path) => {
+                let file = std::fs::OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(path)
+                    .map_err(Error::SerialManager)?;
+                SerialBackend::File(file)
+            }
+            ConsoleOutputMode::Socket(path) => {
+                let _ = std::fs::remove_file(path);
+                let listener = UnixListener::bind(path).map_err(Error::SerialManager)?;
+                listener
+                    .set_nonblocking(true)
+                    .map_err(Error::SerialManager)?;
+                SerialBackend::Socket {
+                    listener,
+                    stream: None,
+                }
+            }
+            ConsoleOutputMode::Pty => {
+                let (master, slave_path) = Self::open_pty()?;
+                SerialBackend::Pty { master, slave_path }
+            }
+        };
+
+        // Wire up the guest -> host direction: bytes the guest writes to its UART's transmit
+        // register are forwarded to the backend synchronously, from inside `SerialDevice::write`,
+        // rather than polled for here. `Socket` has no sink until a client connects, so it is
+        // wired lazily in `pump_backend_to_guest` instead.
+        let sink: Option<Box<dyn Write + Send>> = match &backend {
+            SerialBackend::Off | SerialBackend::Socket { .. } => None,
+            SerialBackend::Tty => Some(Box::new(io::stdout())),
+            SerialBackend::File(file) => {
+                Some(Box::new(file.try_clone().map_err(Error::SerialManager)?))
+            }
+            SerialBackend::Pty { master, .. } => {
+                Some(Box::new(master.try_clone().map_err(Error::SerialManager)?))
+            }
+        };
+        if let Some(sink) = sink {
+            serial.lock().unwrap().set_output_sink(sink);
+        }
+
+        let epoll_fd = Self::create_epoll_fd().map_err(Error::SerialManager)?;
+        let kill_evt = EventFd::new(libc::EFD_NONBLOCK).map_err(Error::EventFd)?;
+
+        Ok(SerialManager {
+            serial,
+            mode,
+            epoll_fd,
+            kill_evt,
+            backend,
+        })
+    }
+
+    /// Path of the PTY slave device, if this manager was created with [`ConsoleOutputMode::Pty`].
+    pub fn pty_slave_path(&self) -> Option<&Path> {
+        match &self.backend {
+            SerialBackend::Pty { slave_path, .. } => Some(slave_path.as_path()),
+            _ => None,
+        }
+    }
+
+    /// An eventfd that, when written to, causes the pump thread spawned by [`Self::start`] to
+    /// exit.
+    pub fn kill_evt(&self) -> Result<EventFd, Error> {
+        self.kill_evt.try_clone().map_err(Error::EventFd)
+    }
+
+    /// Start the epoll-driven thread that pumps bytes from the backend to the guest (the guest
+    /// -> host direction is wired synchronously in [`Self::new`] instead). Returns immediately if
+    /// the backend is [`ConsoleOutputMode::Off`] or [`ConsoleOutputMode::File`], neither of which
+    /// has a host -> guest direction to poll for.
+    pub fn start(self) -> Result<Option<thread::JoinHandle<()>>, Error> {
+        if matches!(self.mode, ConsoleOutputMode::Off | ConsoleOutputMode::File(_)) {
+            return Ok(None);
+        }
+
+        let backend_fd = self
+            .backend
+            .raw_fd()
+            .ok_or_else(|| Error::SerialManager(io::Error::from(io::ErrorKind::Unsupported)))?;
+        Self::epoll_add(self.epoll_fd, backend_fd).map_err(Error::SerialManager)?;
+        Self::epoll_add(self.epoll_fd, self.kill_evt.as_raw_fd()).map_err(Error::SerialManager)?;
+
+        let handle = thread::Builder::new()
+            .name("dragonball_serial_manager".to_owned())
+            .spawn(move || self.run())
+            .map_err(|e| Error::SerialManager(io::Error::new(io::ErrorKind::Other, e)))?;
+
+        Ok(Some(handle))
+    }
+
+    fn run(mut self) {
+        let mut events: [libc::epoll_event; 2] = unsafe { std::mem::zeroed() };
+        loop {
+            let num_events = unsafe {
+                libc::epoll_wait(self.epoll_fd, events.as_mut_ptr(), events.len() as i32, -1)
+            };
+            if num_events < 0 {
+                let err = io::Error::last_os_error();
+                if err.kind() == io::ErrorKind::Interrupted {
+                    continue;
+                }
+                break;
+            }
+
+            for event in events.iter().take(num_events as usize) {
+                let fd = event.u64 as RawFd;
+                if fd == self.kill_evt.as_raw_fd() {
+                    let _ = self.kill_evt.read();
+                    return;
+                }
+                self.pump_backend_to_guest();
+            }
+        }
+    }
+
+    fn pump_backend_to_guest(&mut self) {
+        let mut buf = [0u8; SERIAL_PUMP_BUF_SIZE];
+        let read = match &mut self.backend {
+            SerialBackend::Off | SerialBackend::File(_) => None,
+            SerialBackend::Tty => io::stdin().read(&mut buf).ok(),
+            SerialBackend::Socket { listener, stream } => {
+                if stream.is_none() {
+                    if let Ok((new_stream, _)) = listener.accept() {
+                        if new_stream.set_nonblocking(true).is_ok()
+                            && Self::epoll_add(self.epoll_fd, new_stream.as_raw_fd()).is_ok()
+                        {
+                            if let Ok(sink) = new_stream.try_clone() {
+                                if let Ok(serial) = self.serial.lock() {
+                                    serial.set_output_sink(Box::new(sink));
+                                }
+                            }
+                            *stream = Some(new_stream);
+                        }
+                    }
+                }
+                stream.as_mut().and_then(|s| s.read(&mut buf).ok())
+            }
+            SerialBackend::Pty { master, .. } => master.read(&mut buf).ok(),
+        };
+
+        if let Some(count) = read {
+            if count > 0 {
+                if let Ok(mut serial) = self.serial.lock() {
+                    let _ = serial.raw_input(&buf[..count]);
+                }
+            }
+        }
+    }
+
+    fn open_pty() -> Result<(File, PathBuf), Error> {
+        // SAFETY: posix_openpt/grantpt/unlockpt/ptsname are standard glibc PTY allocation calls
+        // used with an owned fd; return values are checked below.
+        unsafe {
+            let master_fd = libc::posix_openpt(libc::O_RDWR | libc::O_NOCTTY);
+            if master_fd < 0 {
+                return Err(Error::SerialManager(io::Error::last_os_error()));
+            }
+            if libc::grantpt(master_fd) < 0 || libc::unlockpt(master_fd) < 0 {
+                let err = io::Error::last_os_error();
+                libc::close(master_fd);
+                return Err(Error::SerialManager(err));
+            }
+            let slave_name_ptr = libc::ptsname(master_fd);
+            if slave_name_ptr.is_null() {
+                let err = io::Error::last_os_error();
+                libc::close(master_fd);
+                return Err(Error::SerialManager(err));
+            }
+            let slave_path = PathBuf::from(
+                std::ffi::CStr::from_ptr(slave_name_ptr)
+                    .to_string_lossy()
+                    .into_owned(),
+            );
+            Ok((<File as std::os::unix::io::FromRawFd>::from_raw_fd(master_fd), slave_path))
+        }
+    }
+
+    fn create_epoll_fd() -> io::Result<RawFd> {
+        // SAFETY: epoll_create1 returns an owned fd or -1 on error.
+        let fd = unsafe { libc::epoll_create1(libc::EPOLL_CLOEXEC) };
+        if fd < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(fd)
+    }
+
+    fn epoll_add(epoll_fd: RawFd, fd: RawFd) -> io::Result<()> {
+        let mut event = libc::epoll_event {
+            events: libc::EPOLLIN as u32,
+            u64: fd as u64,
+        };
+        // SAFETY: epoll_fd and fd are valid, owned file descriptors for the lifetime of this
+        // call and `event` is a valid pointer to a properly initialized epoll_event.
+        let ret = unsafe { libc::epoll_ctl(epoll_fd, libc::EPOLL_CTL_ADD, fd, &mut event) };
+        if ret < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+}
+
+impl Drop for SerialManager {
+    fn drop(&mut self) {
+        // SAFETY: epoll_fd is an owned fd created by `create_epoll_fd` and not closed elsewhere.
+        unsafe {
+            libc::close(self.epoll_fd);
+        }
+    }
+}
+
+/// Serializable snapshot of a single UART's register file, as needed to pause and later resume
+/// a guest without it observing any discontinuity on the serial line.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UartRegisterState {
+    /// Interrupt Enable Register.
+    pub ier: u8,
+    /// Line Control Register.
+    pub lcr: u8,
+    /// Modem Control Register.
+    pub mcr: u8,
+    /// Line Status Register.
+    pub lsr: u8,
+    /// Baud rate divisor latch.
+    pub divisor_latch: u16,
+    /// Bytes currently buffered in the receive FIFO, in read order.
+    pub fifo: Vec<u8>,
+}
+
+/// Minimal 16550A-compatible UART.
+///
+/// This is implemented locally, rather than pulled in from an external legacy-devices crate, so
+/// that its register file can be captured and restored byte-for-byte by
+/// [`LegacyDeviceManager::save`] -- something an opaque external device type with no accessors
+/// cannot support.
+pub struct SerialDevice {
+    interrupt_evt: EventFd,
+    state: Mutex<UartRegisterState>,
+    output_sink: Mutex<Option<Box<dyn Write + Send>>>,
+}
+
+impl SerialDevice {
+    /// Create a new UART that signals `interrupt_evt` when the guest has data to receive.
+    pub fn new(interrupt_evt: EventFd) -> Self {
+        SerialDevice {
+            interrupt_evt,
+            state: Mutex::new(UartRegisterState::default()),
+            output_sink: Mutex::new(None),
+        }
+    }
+
+    /// Hand the UART a writer that bytes the guest transmits (register offset 0, THR) are
+    /// forwarded to. Used by [`SerialManager`] to connect a UART to its host backend.
+    pub fn set_output_sink(&self, sink: Box<dyn Write + Send>) {
+        *self.output_sink.lock().unwrap() = Some(sink);
+    }
+
+    /// Feed bytes received from the host backend into the guest's receive FIFO, raising the
+    /// UART interrupt if the guest has receive-data interrupts enabled (IER bit 0).
+    pub fn raw_input(&self, data: &[u8]) -> io::Result<()> {
+        let mut state = self.state.lock().unwrap();
+        state.fifo.extend_from_slice(data);
+        let ier = state.ier;
+        drop(state);
+        if ier & 0x1 != 0 {
+            self.interrupt_evt.write(1)?;
+        }
+        Ok(())
+    }
+
+    /// Capture the UART's current register file.
+    pub fn save_state(&self) -> UartRegisterState {
+        self.state.lock().unwrap().clone()
+    }
+
+    /// Overwrite the UART's register file, e.g. after reconstructing it from a snapshot.
+    pub fn restore_state(&self, state: &UartRegisterState) {
+        *self.state.lock().unwrap() = state.clone();
+    }
+}
+
+impl dbs_device::DeviceIo for SerialDevice {
+    fn read(&self, _base: dbs_device::IoAddress, offset: dbs_device::IoAddress, data: &mut [u8]) {
+        let out = match data.get_mut(0) {
+            Some(out) => out,
+            None => return,
+        };
+        let mut state = self.state.lock().unwrap();
+        *out = match offset.raw_value() {
+            0 if state.lcr & 0x80 != 0 => (state.divisor_latch & 0xff) as u8,
+            0 => {
+                if state.fifo.is_empty() {
+                    0
+                } else {
+                    state.fifo.remove(0)
+                }
+            }
+            1 if state.lcr & 0x80 != 0 => (state.divisor_latch >> 8) as u8,
+            1 => state.ier,
+            4 => state.mcr,
+            // THR always empty and the transmitter always idle: writes are forwarded to the
+            // host backend synchronously, so there is never any transmit backlog to report.
+            5 if state.fifo.is_empty() => 0x60,
+            5 => 0x61,
+            _ => 0,
+        };
+    }
+
+    fn write(&self, _base: dbs_device::IoAddress, offset: dbs_device::IoAddress, data: &[u8]) {
+        let byte = match data.first() {
+            Some(&byte) => byte,
+            None => return,
+        };
+        let reg = offset.raw_value();
+        if reg == 0 {
+            let dlab = {
+                let mut state = self.state.lock().unwrap();
+                let dlab = state.lcr & 0x80 != 0;
+                if dlab {
+                    state.divisor_latch = (state.divisor_latch & 0xff00) | byte as u16;
+                }
+                dlab
+            };
+            if !dlab {
+                if let Some(sink) = self.output_sink.lock().unwrap().as_mut() {
+                    let _ = sink.write_all(&[byte]);
+                    let _ = sink.flush();
+                }
+            }
+            return;
+        }
+        let mut state = self.state.lock().unwrap();
+        match reg {
+            1 if state.lcr & 0x80 != 0 => {
+                state.divisor_latch = (state.divisor_latch & 0x00ff) | ((byte as u16) << 8)
+            }
+            1 => state.ier = byte,
+            3 => state.lcr = byte,
+            4 => state.mcr = byte,
+            _ => {}
+        }
+    }
+}
+
+/// Serializable snapshot of a PL031 real-time clock's registers.
+#[cfg(target_arch = "aarch64")]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RtcRegisterState {
+    /// Match register (RTCMR).
+    pub match_value: u32,
+    /// Load register (RTCLR).
+    pub load: u32,
+    /// Control register (RTCCR).
+    pub control: u32,
+    /// Offset, in seconds, applied to the free-running counter to reproduce RTCDR on restore.
+    pub counter_offset: i64,
+}
+
+// PL031 register offsets relevant to save/restore; interrupt-related registers are not modeled.
+#[cfg(target_arch = "aarch64")]
+const PL031_REG_DATA: u64 = 0x00;
+#[cfg(target_arch = "aarch64")]
+const PL031_REG_MATCH: u64 = 0x04;
+#[cfg(target_arch = "aarch64")]
+const PL031_REG_LOAD: u64 = 0x08;
+#[cfg(target_arch = "aarch64")]
+const PL031_REG_CONTROL: u64 = 0x0c;
+
+/// Minimal PL031 real-time clock.
+///
+/// This is implemented locally, rather than pulled in from an external legacy-devices crate, so
+/// that its register file can be captured and restored byte-for-byte by
+/// [`LegacyDeviceManager::save`].
+#[cfg(target_arch = "aarch64")]
+pub struct RTCDevice {
+    state: Mutex<RtcRegisterState>,
+}
+
+#[cfg(target_arch = "aarch64")]
+impl RTCDevice {
+    /// Create a new RTC whose free-running counter tracks the host's wall-clock time.
+    pub fn new() -> Self {
+        RTCDevice {
+            state: Mutex::new(RtcRegisterState::default()),
+        }
+    }
+
+    /// Capture the RTC's current register file.
+    pub fn save_state(&self) -> RtcRegisterState {
+        self.state.lock().unwrap().clone()
+    }
+
+    /// Overwrite the RTC's register file, e.g. after reconstructing it from a snapshot.
+    pub fn restore_state(&self, state: &RtcRegisterState) {
+        *self.state.lock().unwrap() = state.clone();
+    }
+
+    fn now_secs() -> u32 {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs() as u32)
+            .unwrap_or(0)
+    }
+}
+
+#[cfg(target_arch = "aarch64")]
+impl Default for RTCDevice {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(target_arch = "aarch64")]
+impl dbs_device::DeviceIo for RTCDevice {
+    fn read(&self, _base: dbs_device::IoAddress, offset: dbs_device::IoAddress, data: &mut [u8]) {
+        let word = match data.get_mut(0..4) {
+            Some(word) => word,
+            None => return,
+        };
+        let state = self.state.lock().unwrap();
+        let value = match offset.raw_value() {
+            PL031_REG_DATA => (Self::now_secs() as i64 + state.counter_offset) as u32,
+            PL031_REG_MATCH => state.match_value,
+            PL031_REG_LOAD => state.load,
+            PL031_REG_CONTROL => state.control,
+            _ => 0,
+        };
+        word.copy_from_slice(&value.to_le_bytes());
+    }
+
+    fn write(&self, _base: dbs_device::IoAddress, offset: dbs_device::IoAddress, data: &[u8]) {
+        let word = match data.get(0..4) {
+            Some(word) => word,
+            None => return,
+        };
+        let value = u32::from_le_bytes([word[0], word[1], word[2], word[3]]);
+        let mut state = self.state.lock().unwrap();
+        match offset.raw_value() {
+            PL031_REG_MATCH => state.match_value = value,
+            PL031_REG_LOAD => {
+                state.load = value;
+                state.counter_offset = value as i64 - Self::now_secs() as i64;
+            }
+            PL031_REG_CONTROL => state.control = value,
+            _ => {}
+        }
+    }
+}
+
+// i8042 controller commands (written to the command/status port).
+#[cfg(target_arch = "x86_64")]
+const I8042_CMD_KBD_DISABLE: u8 = 0xad;
+#[cfg(target_arch = "x86_64")]
+const I8042_CMD_KBD_ENABLE: u8 = 0xae;
+#[cfg(target_arch = "x86_64")]
+const I8042_CMD_READ_CTRL_BYTE: u8 = 0x20;
+// Status register bit set while a byte is waiting to be read from the data port.
+#[cfg(target_arch = "x86_64")]
+const I8042_STATUS_OUTPUT_FULL: u8 = 0x01;
+// Controller command byte reported in response to `I8042_CMD_READ_CTRL_BYTE`: keyboard interrupt
+// enabled (bit 0) and keyboard not disabled (bit 4 clear), which is enough for the Linux atkbd
+// driver's probe sequence to proceed.
+#[cfg(target_arch = "x86_64")]
+const I8042_CTRL_BYTE: u8 = 0x01;
+// ACK byte returned for any data-port command the guest sends to the keyboard itself.
+#[cfg(target_arch = "x86_64")]
+const I8042_KBD_ACK: u8 = 0xfa;
+// Legacy CPU-reset command: the only command the external legacy-devices crate's i8042 device
+// used to honor. Kept working here so guests that pulse the keyboard controller's reset line
+// (rather than using the newer ACPI shutdown device) still observe a reset request.
+#[cfg(target_arch = "x86_64")]
+const I8042_CMD_RESET_CPU: u8 = 0xfe;
+
+/// Minimal i8042 PS/2 controller with a working keyboard data path.
+///
+/// This is implemented locally, rather than pulled in from an external legacy-devices crate,
+/// because that crate's i8042 device only honors the `0xfe` CPU-reset command and has no
+/// keyboard input path at all.
+#[cfg(target_arch = "x86_64")]
+pub struct I8042Device {
+    kbd_interrupt_evt: EventFd,
+    reset_evt: EventFd,
+    output_buffer: Mutex<VecDeque<u8>>,
+    keyboard_enabled: Mutex<bool>,
+}
+
+#[cfg(target_arch = "x86_64")]
+impl I8042Device {
+    /// Create a new i8042 controller that raises `kbd_interrupt_evt` whenever a byte becomes
+    /// available to read from the data port, and signals `reset_evt` when the guest writes the
+    /// `0xfe` CPU-reset command to the command port.
+    pub fn new(kbd_interrupt_evt: EventFd, reset_evt: EventFd) -> Self {
+        I8042Device {
+            kbd_interrupt_evt,
+            reset_evt,
+            output_buffer: Mutex::new(VecDeque::new()),
+            keyboard_enabled: Mutex::new(true),
+        }
+    }
+
+    /// Inject a sequence of Set-1 scancode bytes into the keyboard's output buffer, as if a key
+    /// had been pressed on a PS/2 keyboard attached to this controller, and raise the keyboard
+    /// IRQ so the guest knows to read them. While the keyboard is disabled (command `0xad`) the
+    /// scancodes are dropped and no IRQ is raised.
+    pub fn trigger_key(&self, scancodes: &[u8]) {
+        if !*self.keyboard_enabled.lock().unwrap() {
+            return;
+        }
+        self.output_buffer.lock().unwrap().extend(scancodes.iter().copied());
+        let _ = self.kbd_interrupt_evt.write(1);
+    }
+
+    fn enqueue(&self, byte: u8) {
+        self.output_buffer.lock().unwrap().push_back(byte);
+        let _ = self.kbd_interrupt_evt.write(1);
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+impl dbs_device::DeviceIo for I8042Device {
+    fn read(&self, _base: dbs_device::IoAddress, offset: dbs_device::IoAddress, data: &mut [u8]) {
+        let out = match data.get_mut(0) {
+            Some(out) => out,
+            None => return,
+        };
+        *out = match offset.raw_value() {
+            0 => self.output_buffer.lock().unwrap().pop_front().unwrap_or(0),
+            I8042_COMMAND_PORT_OFFSET => {
+                if self.output_buffer.lock().unwrap().is_empty() {
+                    0
+                } else {
+                    I8042_STATUS_OUTPUT_FULL
+                }
+            }
+            _ => 0,
+        };
+    }
+
+    fn write(&self, _base: dbs_device::IoAddress, offset: dbs_device::IoAddress, data: &[u8]) {
+        let byte = match data.first() {
+            Some(&byte) => byte,
+            None => return,
+        };
+        match offset.raw_value() {
+            // Every write to the data port is treated as a byte sent to the keyboard itself
+            // (e.g. an LED-set command) and acknowledged; controller commands that take a data
+            // byte are not modeled.
+            0 => self.enqueue(I8042_KBD_ACK),
+            I8042_COMMAND_PORT_OFFSET => match byte {
+                I8042_CMD_KBD_ENABLE => *self.keyboard_enabled.lock().unwrap() = true,
+                I8042_CMD_KBD_DISABLE => *self.keyboard_enabled.lock().unwrap() = false,
+                I8042_CMD_READ_CTRL_BYTE => self.enqueue(I8042_CTRL_BYTE),
+                I8042_CMD_RESET_CPU => {
+                    let _ = self.reset_evt.write(1);
+                }
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+}
+
+/// Serializable state of a [`LegacyDeviceManager`], used to recreate its devices after a
+/// pause/resume cycle or on the target side of a live migration.
+///
+/// Only the com1 and com2 UARTs and (on aarch64) the PL031 RTC are captured. Serial ports beyond
+/// com2 and the i8042 controller are not, and come back in their power-on state on restore.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LegacyDeviceManagerState {
+    /// State of the com1 UART, if one was instantiated.
+    pub com1: Option<UartRegisterState>,
+    /// State of the com2 UART, if one was instantiated.
+    pub com2: Option<UartRegisterState>,
+    /// State of the PL031 RTC, if one was instantiated.
+    #[cfg(target_arch = "aarch64")]
+    pub rtc: Option<RtcRegisterState>,
+}
+
+/// Value written to the ACPI sleep-control register to request the S5 (power-off) sleep state.
+pub const ACPI_SLEEP_STATUS_S5: u8 = 0x5;
+
+/// A minimal ACPI shutdown device exposing a single sleep-control register. Writing
+/// [`ACPI_SLEEP_STATUS_S5`] to it signals `shutdown_evt`, giving ACPI-aware guest firmware a way
+/// to request power-off that works the same way on every architecture.
+pub struct AcpiShutdownDevice {
+    shutdown_evt: EventFd,
+}
+
+impl AcpiShutdownDevice {
+    /// Create a new `AcpiShutdownDevice` that signals `shutdown_evt` on an S5 sleep request.
+    pub fn new(shutdown_evt: EventFd) -> Self {
+        AcpiShutdownDevice { shutdown_evt }
+    }
+}
+
+impl dbs_device::DeviceIo for AcpiShutdownDevice {
+    fn read(&self, _base: dbs_device::IoAddress, _offset: dbs_device::IoAddress, data: &mut [u8]) {
+        data.iter_mut().for_each(|b| *b = 0);
+    }
+
+    fn write(&self, _base: dbs_device::IoAddress, _offset: dbs_device::IoAddress, data: &[u8]) {
+        if data.first() == Some(&ACPI_SLEEP_STATUS_S5) {
+            let _ = self.shutdown_evt.write(1);
+        }
+    }
+}
+
+/// A minimal ACPI Generic Event Device (GED), used to notify the guest of events (such as a
+/// requested shutdown) through a single interrupt-backed notification register.
+pub struct AcpiGEDDevice {
+    notification_evt: EventFd,
+    selector: Mutex<u8>,
+}
+
+impl AcpiGEDDevice {
+    /// Create a new `AcpiGEDDevice` that raises `notification_evt` to signal the guest.
+    pub fn new(notification_evt: EventFd) -> Self {
+        AcpiGEDDevice {
+            notification_evt,
+            selector: Mutex::new(0),
+        }
+    }
+
+    /// Raise the GED interrupt to notify the guest of a pending ACPI event.
+    pub fn notify(&self) -> io::Result<()> {
+        self.notification_evt.write(1)
+    }
+}
+
+impl dbs_device::DeviceIo for AcpiGEDDevice {
+    fn read(&self, _base: dbs_device::IoAddress, _offset: dbs_device::IoAddress, data: &mut [u8]) {
+        if let Some(b) = data.get_mut(0) {
+            *b = *self.selector.lock().unwrap();
+        }
+    }
+
+    fn write(&self, _base: dbs_device::IoAddress, _offset: dbs_device::IoAddress, data: &[u8]) {
+        if let Some(&b) = data.first() {
+            *self.selector.lock().unwrap() = b;
+            let _ = self.notify();
+        }
+    }
 }
 
 /// The `LegacyDeviceManager` is a wrapper that is used for registering legacy devices
 /// on an I/O Bus.
 ///
-/// It currently manages the uart and i8042 devices. The `LegacyDeviceManger` should be initialized
-/// only by using the constructor.
+/// It manages the uart, i8042 and ACPI shutdown/GED devices. Every device other than the ACPI
+/// shutdown/GED pair is optional: a [`LegacyDeviceManagerBuilder`] decides which of them get
+/// instantiated for a given guest. The `LegacyDeviceManger` should be initialized only by using
+/// `create_manager`/`restore` (fixed, backwards-compatible COM1+COM2+i8042/RTC layout) or a
+/// `LegacyDeviceManagerBuilder` (customizable layout).
 pub struct LegacyDeviceManager {
+    pub(crate) shutdown_device: Arc<Mutex<AcpiShutdownDevice>>,
+    shutdown_eventfd: EventFd,
+    pub(crate) ged_device: Arc<Mutex<AcpiGEDDevice>>,
+    _ged_eventfd: EventFd,
+    #[cfg(target_arch = "x86_64")]
+    pub(crate) i8042_device: Option<Arc<Mutex<I8042Device>>>,
     #[cfg(target_arch = "x86_64")]
-    i8042_reset_eventfd: EventFd,
+    _i8042_eventfd: Option<EventFd>,
     #[cfg(target_arch = "aarch64")]
-    pub(crate) _rtc_device: Arc<Mutex<RTCDevice>>,
+    pub(crate) _rtc_device: Option<Arc<Mutex<RTCDevice>>>,
     #[cfg(target_arch = "aarch64")]
-    _rtc_eventfd: EventFd,
-    pub(crate) com1_device: Arc<Mutex<SerialDevice>>,
-    _com1_eventfd: EventFd,
-    pub(crate) com2_device: Arc<Mutex<SerialDevice>>,
-    _com2_eventfd: EventFd,
+    _rtc_eventfd: Option<EventFd>,
+    pub(crate) com1_device: Option<Arc<Mutex<SerialDevice>>>,
+    _com1_eventfd: Option<EventFd>,
+    pub(crate) com2_device: Option<Arc<Mutex<SerialDevice>>>,
+    _com2_eventfd: Option<EventFd>,
+    pub(crate) extra_serial_devices: Vec<Arc<Mutex<SerialDevice>>>,
+    _extra_serial_eventfds: Vec<EventFd>,
+    // Kill switches for the pump threads below; signaling one causes its matching thread's
+    // `SerialManager::run` loop to exit. Used by `Drop` to tear the threads down instead of
+    // leaking them for the life of the process.
+    serial_manager_kill_evts: Vec<EventFd>,
+    // Pump threads for any serial port a `LegacyDeviceManagerBuilder` was asked to connect to a
+    // host console backend. Kept alive for as long as the manager lives.
+    _serial_manager_threads: Vec<thread::JoinHandle<()>>,
+}
+
+impl Drop for LegacyDeviceManager {
+    fn drop(&mut self) {
+        for kill_evt in &self.serial_manager_kill_evts {
+            let _ = kill_evt.write(1);
+        }
+        for handle in self._serial_manager_threads.drain(..) {
+            let _ = handle.join();
+        }
+    }
 }
 
 impl LegacyDeviceManager {
-    /// Get the serial device for com1.
-    pub fn get_com1_serial(&self) -> Arc<Mutex<SerialDevice>> {
+    /// Get the serial device for com1, if one was instantiated.
+    pub fn get_com1_serial(&self) -> Option<Arc<Mutex<SerialDevice>>> {
         self.com1_device.clone()
     }
 
-    /// Get the serial device for com2
-    pub fn get_com2_serial(&self) -> Arc<Mutex<SerialDevice>> {
+    /// Get the serial device for com2, if one was instantiated.
+    pub fn get_com2_serial(&self) -> Option<Arc<Mutex<SerialDevice>>> {
         self.com2_device.clone()
     }
+
+    /// Get every serial port beyond com1/com2 that was requested through a
+    /// [`LegacyDeviceManagerBuilder`].
+    pub fn get_extra_serial_devices(&self) -> &[Arc<Mutex<SerialDevice>>] {
+        &self.extra_serial_devices
+    }
+
+    /// Get the i8042 PS/2 controller device, if one was instantiated.
+    #[cfg(target_arch = "x86_64")]
+    pub fn get_i8042(&self) -> Option<Arc<Mutex<I8042Device>>> {
+        self.i8042_device.clone()
+    }
+
+    /// Inject a sequence of Set-1 scancodes into the guest's keyboard input buffer, as if a key
+    /// had been pressed on a PS/2 keyboard attached to the i8042 controller. Returns `Ok(())`
+    /// even while the guest has the keyboard disabled, in which case the scancodes are dropped.
+    #[cfg(target_arch = "x86_64")]
+    pub fn trigger_key(&self, scancodes: &[u8]) -> std::result::Result<(), Error> {
+        match &self.i8042_device {
+            Some(device) => {
+                device.lock().unwrap().trigger_key(scancodes);
+                Ok(())
+            }
+            None => Err(Error::DeviceNotPresent("i8042")),
+        }
+    }
+
+    /// Get the eventfd signaled when the guest requests a shutdown (S5) through the ACPI
+    /// shutdown device. Unlike the old i8042-only reset notification, this works identically on
+    /// every architecture.
+    pub fn get_reset_eventfd(&self) -> std::result::Result<EventFd, Error> {
+        self.shutdown_eventfd.try_clone().map_err(Error::EventFd)
+    }
+
+    /// Get the ACPI Generic Event Device used to notify the guest of ACPI events.
+    pub fn get_ged_device(&self) -> Arc<Mutex<AcpiGEDDevice>> {
+        self.ged_device.clone()
+    }
+
+    /// Save the state of all legacy devices managed by this `LegacyDeviceManager`.
+    ///
+    /// The returned [`LegacyDeviceManagerState`] can later be handed to `create_manager`'s (or a
+    /// builder's) `restore` counterpart to reconstruct an identical set of devices, registered at
+    /// the same PIO/MMIO ranges and IRQ lines, so the guest observes no discontinuity.
+    pub fn save(&self) -> LegacyDeviceManagerState {
+        LegacyDeviceManagerState {
+            com1: self
+                .com1_device
+                .as_ref()
+                .map(|d| d.lock().unwrap().save_state()),
+            com2: self
+                .com2_device
+                .as_ref()
+                .map(|d| d.lock().unwrap().save_state()),
+            #[cfg(target_arch = "aarch64")]
+            rtc: self
+                ._rtc_device
+                .as_ref()
+                .map(|d| d.lock().unwrap().save_state()),
+        }
+    }
 }
 
 #[cfg(target_arch = "x86_64")]
@@ -71,50 +907,291 @@ pub(crate) mod x86_64 {
     use super::*;
     use dbs_device::device_manager::IoManager;
     use dbs_device::resources::Resource;
-    use dbs_legacy_devices::{EventFdTrigger, I8042Device, I8042DeviceMetrics};
     use kvm_ioctls::VmFd;
 
     pub(crate) const COM1_IRQ: u32 = 4;
     pub(crate) const COM1_PORT1: u16 = 0x3f8;
     pub(crate) const COM2_IRQ: u32 = 3;
     pub(crate) const COM2_PORT1: u16 = 0x2f8;
+    // GSI the i8042 controller's keyboard interrupt is wired to.
+    pub(crate) const I8042_KBD_IRQ: u32 = 1;
+    // GSI the ACPI GED device's notification interrupt is wired to. 9 is the conventional ACPI
+    // SCI line on x86, so this matches what a guest's ACPI tables already expect.
+    pub(crate) const ACPI_GED_IRQ: u32 = 9;
+    // PIO port backing the ACPI shutdown device's sleep-control register. Chosen outside the
+    // conventional VGA range (0x3c0-0x3df) to avoid colliding with a VGA device, in the same
+    // neighborhood as cloud-hypervisor's ACPI PM/GED registers.
+    pub(crate) const ACPI_SHUTDOWN_PORT: u16 = 0x0600;
+    // PIO port backing the ACPI GED device's notification register.
+    pub(crate) const ACPI_GED_PORT: u16 = 0x0604;
 
     type Result<T> = ::std::result::Result<T, Error>;
 
-    impl LegacyDeviceManager {
-        /// Create a LegacyDeviceManager instance handling legacy devices (uart, i8042).
-        pub fn create_manager(bus: &mut IoManager, vm_fd: Option<Arc<VmFd>>) -> Result<Self> {
-            let (com1_device, com1_eventfd) =
-                Self::create_com_device(bus, vm_fd.as_ref(), COM1_IRQ, COM1_PORT1)?;
-            let (com2_device, com2_eventfd) =
-                Self::create_com_device(bus, vm_fd.as_ref(), COM2_IRQ, COM2_PORT1)?;
-
-            let exit_evt = EventFd::new(libc::EFD_NONBLOCK).map_err(Error::EventFd)?;
-            let i8042_device = Arc::new(Mutex::new(I8042Device::new(
-                EventFdTrigger::new(exit_evt.try_clone().map_err(Error::EventFd)?),
-                Arc::new(I8042DeviceMetrics::default()),
-            )));
-            let resources = [Resource::PioAddressRange {
-                // 0x60 and 0x64 are the io ports that i8042 devices used.
-                // We register pio address range from 0x60 - 0x64 with base I8042_DATA_PORT for i8042 to use.
-                base: I8042_DATA_PORT,
-                size: 0x5,
-            }];
-            bus.register_device_io(i8042_device, &resources)
-                .map_err(Error::BusError)?;
+    // Size, in bytes, of the PIO range reserved by a single UART.
+    const UART_PIO_SIZE: u16 = 0x8;
+    // Size, in bytes, of the PIO range reserved by the i8042 controller.
+    const I8042_PIO_SIZE: u16 = 0x5;
+
+    /// Describes a single UART to instantiate: its PIO port base, IRQ line, and (optionally) the
+    /// host backend its console should be connected to.
+    #[derive(Debug, Clone)]
+    pub struct SerialPortDescriptor {
+        /// Base PIO port of the UART's 8-byte register range.
+        pub port_base: u16,
+        /// IRQ line the UART's interrupt is wired to.
+        pub irq: u32,
+        /// Host backend to connect the UART's console to, if any.
+        pub output_mode: Option<ConsoleOutputMode>,
+    }
+
+    impl SerialPortDescriptor {
+        /// Describe a UART at `port_base`/`irq` with no host console backend attached.
+        pub fn new(port_base: u16, irq: u32) -> Self {
+            SerialPortDescriptor {
+                port_base,
+                irq,
+                output_mode: None,
+            }
+        }
+
+        /// Attach a host console backend to this UART.
+        pub fn with_output_mode(mut self, mode: ConsoleOutputMode) -> Self {
+            self.output_mode = Some(mode);
+            self
+        }
+
+        fn pio_range(&self) -> (u16, u16) {
+            (self.port_base, self.port_base + UART_PIO_SIZE)
+        }
+    }
+
+    /// Builder for [`LegacyDeviceManager`] that lets embedders request an arbitrary set of
+    /// serial ports (zero, one, or more than the traditional two) and decide whether the i8042
+    /// controller is instantiated at all, instead of always getting the fixed COM1+COM2+i8042
+    /// layout that `create_manager` sets up.
+    ///
+    /// The first descriptor becomes `com1`, the second becomes `com2`; any further descriptors
+    /// are reachable through [`LegacyDeviceManager::get_extra_serial_devices`].
+    pub struct LegacyDeviceManagerBuilder {
+        serial_ports: Vec<SerialPortDescriptor>,
+        enable_i8042: bool,
+    }
+
+    impl Default for LegacyDeviceManagerBuilder {
+        fn default() -> Self {
+            LegacyDeviceManagerBuilder {
+                serial_ports: vec![
+                    SerialPortDescriptor::new(COM1_PORT1, COM1_IRQ),
+                    SerialPortDescriptor::new(COM2_PORT1, COM2_IRQ),
+                ],
+                enable_i8042: true,
+            }
+        }
+    }
+
+    impl LegacyDeviceManagerBuilder {
+        /// Start from an empty layout: no serial ports, no i8042.
+        pub fn new() -> Self {
+            LegacyDeviceManagerBuilder {
+                serial_ports: Vec::new(),
+                enable_i8042: false,
+            }
+        }
+
+        /// Append a serial port to the layout.
+        pub fn serial_port(mut self, descriptor: SerialPortDescriptor) -> Self {
+            self.serial_ports.push(descriptor);
+            self
+        }
+
+        /// Whether to instantiate the i8042 PS/2 controller. Defaults to `true` on
+        /// [`LegacyDeviceManagerBuilder::default`] and `false` on
+        /// [`LegacyDeviceManagerBuilder::new`].
+        pub fn enable_i8042(mut self, enable: bool) -> Self {
+            self.enable_i8042 = enable;
+            self
+        }
+
+        fn validate(&self) -> Result<()> {
+            let mut pio_ranges: Vec<(u16, u16)> = Vec::new();
+            let mut irqs = std::collections::HashSet::new();
+            if self.enable_i8042 {
+                pio_ranges.push((I8042_DATA_PORT, I8042_DATA_PORT + I8042_PIO_SIZE));
+            }
+            for serial in &self.serial_ports {
+                let range = serial.pio_range();
+                if pio_ranges
+                    .iter()
+                    .any(|&(s, e)| range.0 < e && s < range.1)
+                {
+                    return Err(Error::ResourceOverlap(range.0 as u64));
+                }
+                pio_ranges.push(range);
+                if !irqs.insert(serial.irq) {
+                    return Err(Error::IrqOverlap(serial.irq));
+                }
+            }
+            Ok(())
+        }
+
+        /// Register every requested device on `bus` and build the resulting
+        /// `LegacyDeviceManager`.
+        pub fn build(self, bus: &mut IoManager, vm_fd: Option<Arc<VmFd>>) -> Result<LegacyDeviceManager> {
+            self.validate()?;
+            self.finish(bus, vm_fd, None)
+        }
+
+        /// Like [`Self::build`], but restores register state from a previously
+        /// [`LegacyDeviceManager::save`]d snapshot instead of starting from power-on defaults.
+        /// Only the devices captured in [`LegacyDeviceManagerState`] are restored; any other
+        /// device starts from its power-on defaults. The builder must describe the exact same
+        /// layout that produced `state`.
+        pub fn restore(
+            self,
+            bus: &mut IoManager,
+            vm_fd: Option<Arc<VmFd>>,
+            state: &LegacyDeviceManagerState,
+        ) -> Result<LegacyDeviceManager> {
+            self.validate()?;
+            self.finish(bus, vm_fd, Some(state))
+        }
+
+        fn finish(
+            self,
+            bus: &mut IoManager,
+            vm_fd: Option<Arc<VmFd>>,
+            state: Option<&LegacyDeviceManagerState>,
+        ) -> Result<LegacyDeviceManager> {
+            let mut serial_devices = Vec::with_capacity(self.serial_ports.len());
+            let mut serial_manager_threads = Vec::new();
+            let mut serial_manager_kill_evts = Vec::new();
+            for descriptor in &self.serial_ports {
+                let (device, eventfd) = Self::create_com_device(
+                    bus,
+                    vm_fd.as_ref(),
+                    descriptor.irq,
+                    descriptor.port_base,
+                )?;
+                if let Some(mode) = &descriptor.output_mode {
+                    let manager = SerialManager::new(device.clone(), mode.clone())?;
+                    let kill_evt = manager.kill_evt()?;
+                    if let Some(handle) = manager.start()? {
+                        serial_manager_kill_evts.push(kill_evt);
+                        serial_manager_threads.push(handle);
+                    }
+                }
+                serial_devices.push((device, eventfd));
+            }
+
+            let mut iter = serial_devices.into_iter();
+            let (com1_device, com1_eventfd) = match iter.next() {
+                Some((device, eventfd)) => {
+                    if let Some(state) = state.and_then(|s| s.com1.as_ref()) {
+                        device.lock().unwrap().restore_state(state);
+                    }
+                    (Some(device), Some(eventfd))
+                }
+                None => (None, None),
+            };
+            let (com2_device, com2_eventfd) = match iter.next() {
+                Some((device, eventfd)) => {
+                    if let Some(state) = state.and_then(|s| s.com2.as_ref()) {
+                        device.lock().unwrap().restore_state(state);
+                    }
+                    (Some(device), Some(eventfd))
+                }
+                None => (None, None),
+            };
+            let (extra_serial_devices, extra_serial_eventfds): (Vec<_>, Vec<_>) = iter.unzip();
+
+            let (shutdown_device, shutdown_eventfd, ged_device, ged_eventfd) = Self::create_acpi_devices(
+                bus,
+                vm_fd.as_ref(),
+                ACPI_SHUTDOWN_PORT,
+                ACPI_GED_PORT,
+            )?;
+
+            let (i8042_device, i8042_eventfd) = if self.enable_i8042 {
+                let kbd_evt = EventFd::new(libc::EFD_NONBLOCK).map_err(Error::EventFd)?;
+                let reset_evt = shutdown_eventfd.try_clone().map_err(Error::EventFd)?;
+                let device = Arc::new(Mutex::new(I8042Device::new(
+                    kbd_evt.try_clone().map_err(Error::EventFd)?,
+                    reset_evt,
+                )));
+                let resources = [Resource::PioAddressRange {
+                    // 0x60 and 0x64 are the io ports that i8042 devices used.
+                    // We register pio address range from 0x60 - 0x64 with base I8042_DATA_PORT for i8042 to use.
+                    base: I8042_DATA_PORT,
+                    size: 0x5,
+                }];
+                bus.register_device_io(device.clone(), &resources)
+                    .map_err(Error::BusError)?;
+                if let Some(fd) = vm_fd.as_ref() {
+                    fd.register_irqfd(&kbd_evt, I8042_KBD_IRQ)
+                        .map_err(Error::IrqManager)?;
+                }
+                (Some(device), Some(kbd_evt))
+            } else {
+                (None, None)
+            };
 
             Ok(LegacyDeviceManager {
-                i8042_reset_eventfd: exit_evt,
+                shutdown_device,
+                shutdown_eventfd,
+                ged_device,
+                _ged_eventfd: ged_eventfd,
+                i8042_device,
+                _i8042_eventfd: i8042_eventfd,
                 com1_device,
                 _com1_eventfd: com1_eventfd,
                 com2_device,
                 _com2_eventfd: com2_eventfd,
+                extra_serial_devices,
+                _extra_serial_eventfds: extra_serial_eventfds,
+                serial_manager_kill_evts,
+                _serial_manager_threads: serial_manager_threads,
             })
         }
 
-        /// Get the eventfd for exit notification.
-        pub fn get_reset_eventfd(&self) -> Result<EventFd> {
-            self.i8042_reset_eventfd.try_clone().map_err(Error::EventFd)
+        fn create_acpi_devices(
+            bus: &mut IoManager,
+            vm_fd: Option<&Arc<VmFd>>,
+            shutdown_port: u16,
+            ged_port: u16,
+        ) -> Result<(
+            Arc<Mutex<AcpiShutdownDevice>>,
+            EventFd,
+            Arc<Mutex<AcpiGEDDevice>>,
+            EventFd,
+        )> {
+            let shutdown_eventfd = EventFd::new(libc::EFD_NONBLOCK).map_err(Error::EventFd)?;
+            let shutdown_device = Arc::new(Mutex::new(AcpiShutdownDevice::new(
+                shutdown_eventfd.try_clone().map_err(Error::EventFd)?,
+            )));
+            let resources = [Resource::PioAddressRange {
+                base: shutdown_port,
+                size: 0x1,
+            }];
+            bus.register_device_io(shutdown_device.clone(), &resources)
+                .map_err(Error::BusError)?;
+
+            let ged_eventfd = EventFd::new(libc::EFD_NONBLOCK).map_err(Error::EventFd)?;
+            let ged_device = Arc::new(Mutex::new(AcpiGEDDevice::new(
+                ged_eventfd.try_clone().map_err(Error::EventFd)?,
+            )));
+            let resources = [Resource::PioAddressRange {
+                base: ged_port,
+                size: 0x1,
+            }];
+            bus.register_device_io(ged_device.clone(), &resources)
+                .map_err(Error::BusError)?;
+
+            if let Some(fd) = vm_fd {
+                fd.register_irqfd(&ged_eventfd, ACPI_GED_IRQ)
+                    .map_err(Error::IrqManager)?;
+            }
+
+            Ok((shutdown_device, shutdown_eventfd, ged_device, ged_eventfd))
         }
 
         fn create_com_device(
@@ -144,13 +1221,35 @@ pub(crate) mod x86_64 {
             Ok((device, eventfd))
         }
     }
+
+    impl LegacyDeviceManager {
+        /// Create a LegacyDeviceManager instance handling the traditional fixed legacy device
+        /// layout (COM1, COM2, i8042, ACPI shutdown/GED). Equivalent to
+        /// `LegacyDeviceManagerBuilder::default().build(bus, vm_fd)`; kept for callers that don't
+        /// need a custom layout.
+        pub fn create_manager(bus: &mut IoManager, vm_fd: Option<Arc<VmFd>>) -> Result<Self> {
+            LegacyDeviceManagerBuilder::default().build(bus, vm_fd)
+        }
+
+        /// Recreate a `LegacyDeviceManager` from a previously [`LegacyDeviceManager::save`]d
+        /// state, re-registering every device at the exact same PIO ranges and IRQ lines used by
+        /// `create_manager` so the guest sees no discontinuity. Equivalent to
+        /// `LegacyDeviceManagerBuilder::default().restore(bus, vm_fd, state)`.
+        pub fn restore(
+            bus: &mut IoManager,
+            vm_fd: Option<Arc<VmFd>>,
+            state: &LegacyDeviceManagerState,
+        ) -> Result<Self> {
+            LegacyDeviceManagerBuilder::default().restore(bus, vm_fd, state)
+        }
+    }
 }
 
 #[cfg(target_arch = "aarch64")]
 pub(crate) mod aarch64 {
     use super::*;
     use dbs_device::device_manager::{IoManager};
-    use dbs_device::resources::DeviceResources;
+    use dbs_device::resources::{DeviceResources, Resource};
     use std::collections::HashMap;
     use kvm_ioctls::VmFd;
 
@@ -163,30 +1262,227 @@ pub(crate) mod aarch64 {
     /// LegacyDeviceType: rtc
     pub const RTC: &str = "rtc";
 
-    impl LegacyDeviceManager {
-        /// Create a LegacyDeviceManager instance handling legacy devices.
-        pub fn create_manager(
+    // MMIO base backing the ACPI shutdown device's sleep-control register.
+    pub(crate) const ACPI_SHUTDOWN_MMIO_BASE: u64 = 0x0908_0000;
+    // MMIO base backing the ACPI GED device's notification register.
+    pub(crate) const ACPI_GED_MMIO_BASE: u64 = 0x0908_1000;
+    // GSI used to signal ACPI GED events to the guest.
+    pub(crate) const ACPI_GED_IRQ: u32 = 5;
+
+    /// Builder for [`LegacyDeviceManager`] that lets embedders request an arbitrary set of
+    /// serial ports (by their key in the `resources` map passed to
+    /// [`LegacyDeviceManagerBuilder::build`]) and decide whether the PL031 RTC is instantiated,
+    /// instead of always getting the fixed COM1+COM2+RTC layout that `create_manager` sets up.
+    ///
+    /// The first key becomes `com1`, the second becomes `com2`; any further keys are reachable
+    /// through [`LegacyDeviceManager::get_extra_serial_devices`].
+    pub struct LegacyDeviceManagerBuilder {
+        serial_keys: Vec<String>,
+        enable_rtc: bool,
+    }
+
+    impl Default for LegacyDeviceManagerBuilder {
+        fn default() -> Self {
+            LegacyDeviceManagerBuilder {
+                serial_keys: vec![COM1.to_owned(), COM2.to_owned()],
+                enable_rtc: true,
+            }
+        }
+    }
+
+    impl LegacyDeviceManagerBuilder {
+        /// Start from an empty layout: no serial ports, no RTC.
+        pub fn new() -> Self {
+            LegacyDeviceManagerBuilder {
+                serial_keys: Vec::new(),
+                enable_rtc: false,
+            }
+        }
+
+        /// Add a serial port, sourcing its MMIO range and IRQ from `resources[key]` at build
+        /// time.
+        pub fn serial_port(mut self, key: impl Into<String>) -> Self {
+            self.serial_keys.push(key.into());
+            self
+        }
+
+        /// Whether to instantiate the PL031 RTC. Defaults to `true` on
+        /// [`LegacyDeviceManagerBuilder::default`] and `false` on
+        /// [`LegacyDeviceManagerBuilder::new`].
+        pub fn enable_rtc(mut self, enable: bool) -> Self {
+            self.enable_rtc = enable;
+            self
+        }
+
+        fn resolve<'a>(
+            &self,
+            key: &str,
+            resources: &'a HashMap<String, DeviceResources>,
+        ) -> Result<&'a DeviceResources> {
+            resources
+                .get(key)
+                .ok_or_else(|| Error::MissingResource(key.to_owned()))
+        }
+
+        fn validate(&self, resources: &HashMap<String, DeviceResources>) -> Result<()> {
+            let mut ranges: Vec<(u64, u64)> = Vec::new();
+            let mut irqs = std::collections::HashSet::new();
+            let mut keys: Vec<&str> = self.serial_keys.iter().map(String::as_str).collect();
+            if self.enable_rtc {
+                keys.push(RTC);
+            }
+            for key in keys {
+                let res = self.resolve(key, resources)?;
+                for resource in res.get_all_resources() {
+                    if let Some(range) = resource_range(resource) {
+                        if ranges.iter().any(|&(s, e)| range.0 < e && s < range.1) {
+                            return Err(Error::ResourceOverlap(range.0));
+                        }
+                        ranges.push(range);
+                    }
+                }
+                if let Some(irq) = res.get_legacy_irq() {
+                    if !irqs.insert(irq) {
+                        return Err(Error::IrqOverlap(irq));
+                    }
+                }
+            }
+            Ok(())
+        }
+
+        /// Register every requested device on `bus` and build the resulting
+        /// `LegacyDeviceManager`.
+        pub fn build(
+            self,
             bus: &mut IoManager,
             vm_fd: Option<Arc<VmFd>>,
             resources: &HashMap<String, DeviceResources>,
-        ) -> Result<Self> {
-            let (com1_device, com1_eventfd) =
-                Self::create_com_device(bus, vm_fd.as_ref(), resources.get(COM1).unwrap())?;
-            let (com2_device, com2_eventfd) =
-                Self::create_com_device(bus, vm_fd.as_ref(), resources.get(COM2).unwrap())?;
-            let (rtc_device, rtc_eventfd) =
-                Self::create_rtc_device(bus, vm_fd.as_ref(), resources.get(RTC).unwrap())?;
+        ) -> Result<LegacyDeviceManager> {
+            self.validate(resources)?;
+            self.finish(bus, vm_fd, resources, None)
+        }
+
+        /// Like [`Self::build`], but restores register state from a previously
+        /// [`LegacyDeviceManager::save`]d snapshot instead of starting from power-on defaults.
+        /// Only the devices captured in [`LegacyDeviceManagerState`] are restored; any other
+        /// device starts from its power-on defaults. The builder must describe the exact same
+        /// layout that produced `state`.
+        pub fn restore(
+            self,
+            bus: &mut IoManager,
+            vm_fd: Option<Arc<VmFd>>,
+            resources: &HashMap<String, DeviceResources>,
+            state: &LegacyDeviceManagerState,
+        ) -> Result<LegacyDeviceManager> {
+            self.validate(resources)?;
+            self.finish(bus, vm_fd, resources, Some(state))
+        }
+
+        fn finish(
+            self,
+            bus: &mut IoManager,
+            vm_fd: Option<Arc<VmFd>>,
+            resources: &HashMap<String, DeviceResources>,
+            state: Option<&LegacyDeviceManagerState>,
+        ) -> Result<LegacyDeviceManager> {
+            let mut serial_devices = Vec::with_capacity(self.serial_keys.len());
+            for key in &self.serial_keys {
+                let res = self.resolve(key, resources)?;
+                serial_devices.push(Self::create_com_device(bus, vm_fd.as_ref(), res)?);
+            }
+
+            let mut iter = serial_devices.into_iter();
+            let (com1_device, com1_eventfd) = match iter.next() {
+                Some((device, eventfd)) => {
+                    if let Some(state) = state.and_then(|s| s.com1.as_ref()) {
+                        device.lock().unwrap().restore_state(state);
+                    }
+                    (Some(device), Some(eventfd))
+                }
+                None => (None, None),
+            };
+            let (com2_device, com2_eventfd) = match iter.next() {
+                Some((device, eventfd)) => {
+                    if let Some(state) = state.and_then(|s| s.com2.as_ref()) {
+                        device.lock().unwrap().restore_state(state);
+                    }
+                    (Some(device), Some(eventfd))
+                }
+                None => (None, None),
+            };
+            let (extra_serial_devices, extra_serial_eventfds): (Vec<_>, Vec<_>) = iter.unzip();
+
+            let (rtc_device, rtc_eventfd) = if self.enable_rtc {
+                let res = self.resolve(RTC, resources)?;
+                let (device, eventfd) = Self::create_rtc_device(bus, vm_fd.as_ref(), res)?;
+                if let Some(state) = state.and_then(|s| s.rtc.as_ref()) {
+                    device.lock().unwrap().restore_state(state);
+                }
+                (Some(device), Some(eventfd))
+            } else {
+                (None, None)
+            };
+
+            let (shutdown_device, shutdown_eventfd, ged_device, ged_eventfd) =
+                Self::create_acpi_devices(bus, vm_fd.as_ref())?;
 
             Ok(LegacyDeviceManager {
+                shutdown_device,
+                shutdown_eventfd,
+                ged_device,
+                _ged_eventfd: ged_eventfd,
                 _rtc_device: rtc_device,
                 _rtc_eventfd: rtc_eventfd,
                 com1_device,
                 _com1_eventfd: com1_eventfd,
                 com2_device,
                 _com2_eventfd: com2_eventfd,
+                extra_serial_devices,
+                _extra_serial_eventfds: extra_serial_eventfds,
+                serial_manager_kill_evts: Vec::new(),
+                _serial_manager_threads: Vec::new(),
             })
         }
 
+        fn create_acpi_devices(
+            bus: &mut IoManager,
+            vm_fd: Option<&Arc<VmFd>>,
+        ) -> Result<(
+            Arc<Mutex<AcpiShutdownDevice>>,
+            EventFd,
+            Arc<Mutex<AcpiGEDDevice>>,
+            EventFd,
+        )> {
+            let shutdown_eventfd = EventFd::new(libc::EFD_NONBLOCK).map_err(Error::EventFd)?;
+            let shutdown_device = Arc::new(Mutex::new(AcpiShutdownDevice::new(
+                shutdown_eventfd.try_clone().map_err(Error::EventFd)?,
+            )));
+            let resources = [Resource::MmioAddressRange {
+                base: ACPI_SHUTDOWN_MMIO_BASE,
+                size: 0x1000,
+            }];
+            bus.register_device_io(shutdown_device.clone(), &resources)
+                .map_err(Error::BusError)?;
+
+            let ged_eventfd = EventFd::new(libc::EFD_NONBLOCK).map_err(Error::EventFd)?;
+            let ged_device = Arc::new(Mutex::new(AcpiGEDDevice::new(
+                ged_eventfd.try_clone().map_err(Error::EventFd)?,
+            )));
+            let resources = [Resource::MmioAddressRange {
+                base: ACPI_GED_MMIO_BASE,
+                size: 0x1000,
+            }];
+            bus.register_device_io(ged_device.clone(), &resources)
+                .map_err(Error::BusError)?;
+
+            if let Some(fd) = vm_fd {
+                fd.register_irqfd(&ged_eventfd, ACPI_GED_IRQ)
+                    .map_err(Error::IrqManager)?;
+            }
+
+            Ok((shutdown_device, shutdown_eventfd, ged_device, ged_eventfd))
+        }
+
         fn create_com_device(
             bus: &mut IoManager,
             vm_fd: Option<&Arc<VmFd>>,
@@ -201,7 +1497,9 @@ pub(crate) mod aarch64 {
                 .map_err(Error::BusError)?;
 
             if let Some(fd) = vm_fd {
-                let irq = resources.get_legacy_irq().unwrap();
+                let irq = resources
+                    .get_legacy_irq()
+                    .ok_or_else(|| Error::MissingResource("serial device irq".to_owned()))?;
                 fd.register_irqfd(&eventfd, irq)
                     .map_err(Error::IrqManager)?;
             }
@@ -221,7 +1519,9 @@ pub(crate) mod aarch64 {
                 .map_err(Error::BusError)?;
 
             if let Some(fd) = vm_fd {
-                let irq = resources.get_legacy_irq().unwrap();
+                let irq = resources
+                    .get_legacy_irq()
+                    .ok_or_else(|| Error::MissingResource("rtc device irq".to_owned()))?;
                 fd.register_irqfd(&eventfd, irq)
                     .map_err(Error::IrqManager)?;
             }
@@ -229,11 +1529,51 @@ pub(crate) mod aarch64 {
             Ok((device, eventfd))
         }
     }
+
+    impl LegacyDeviceManager {
+        /// Create a LegacyDeviceManager instance handling the traditional fixed legacy device
+        /// layout (COM1, COM2, RTC, ACPI shutdown/GED). Equivalent to
+        /// `LegacyDeviceManagerBuilder::default().build(bus, vm_fd, resources)`; kept for
+        /// callers that don't need a custom layout.
+        pub fn create_manager(
+            bus: &mut IoManager,
+            vm_fd: Option<Arc<VmFd>>,
+            resources: &HashMap<String, DeviceResources>,
+        ) -> Result<Self> {
+            LegacyDeviceManagerBuilder::default().build(bus, vm_fd, resources)
+        }
+
+        /// Recreate a `LegacyDeviceManager` from a previously [`LegacyDeviceManager::save`]d
+        /// state, re-registering every device at the exact same MMIO ranges and IRQ lines used
+        /// by `create_manager` so the guest sees no discontinuity. Equivalent to
+        /// `LegacyDeviceManagerBuilder::default().restore(bus, vm_fd, resources, state)`.
+        pub fn restore(
+            bus: &mut IoManager,
+            vm_fd: Option<Arc<VmFd>>,
+            resources: &HashMap<String, DeviceResources>,
+            state: &LegacyDeviceManagerState,
+        ) -> Result<Self> {
+            LegacyDeviceManagerBuilder::default().restore(bus, vm_fd, resources, state)
+        }
+    }
+
+    /// Compute the `[start, end)` address range covered by a resource, for overlap checks. Only
+    /// PIO/MMIO ranges are relevant here; other resource kinds (e.g. legacy IRQs, which are
+    /// checked separately) are ignored.
+    fn resource_range(resource: &Resource) -> Option<(u64, u64)> {
+        match resource {
+            Resource::PioAddressRange { base, size } => {
+                Some((*base as u64, *base as u64 + *size as u64))
+            }
+            Resource::MmioAddressRange { base, size } => Some((*base, *base + *size)),
+            _ => None,
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    #[cfg(target_arch = "x86_64")]
+    #[cfg(any(target_arch = "x86_64", target_arch = "aarch64"))]
     use super::*;
 
     #[test]
@@ -243,4 +1583,130 @@ mod tests {
         let mgr = LegacyDeviceManager::create_manager(&mut bus, None).unwrap();
         let _exit_fd = mgr.get_reset_eventfd().unwrap();
     }
+
+    #[test]
+    #[cfg(target_arch = "x86_64")]
+    fn test_save_restore_round_trip() {
+        let mut bus = dbs_device::device_manager::IoManager::new();
+        let mgr = LegacyDeviceManager::create_manager(&mut bus, None).unwrap();
+
+        let com1 = mgr.get_com1_serial().unwrap();
+        com1.lock().unwrap().restore_state(&UartRegisterState {
+            ier: 0x01,
+            lcr: 0x03,
+            mcr: 0x0b,
+            lsr: 0x60,
+            divisor_latch: 0x0c,
+            fifo: vec![1, 2, 3],
+        });
+        let state = mgr.save();
+
+        let mut restore_bus = dbs_device::device_manager::IoManager::new();
+        let restored = LegacyDeviceManager::restore(&mut restore_bus, None, &state).unwrap();
+        assert_eq!(restored.save(), state);
+    }
+
+    #[test]
+    #[cfg(target_arch = "x86_64")]
+    fn test_builder_rejects_overlapping_pio_ranges() {
+        use super::x86_64::{LegacyDeviceManagerBuilder, SerialPortDescriptor};
+
+        let mut bus = dbs_device::device_manager::IoManager::new();
+        let builder = LegacyDeviceManagerBuilder::new()
+            .serial_port(SerialPortDescriptor::new(0x3f8, 4))
+            .serial_port(SerialPortDescriptor::new(0x3f8, 5));
+        let err = builder.build(&mut bus, None).unwrap_err();
+        assert!(matches!(err, Error::ResourceOverlap(0x3f8)));
+    }
+
+    #[test]
+    #[cfg(target_arch = "x86_64")]
+    fn test_builder_rejects_colliding_irqs() {
+        use super::x86_64::{LegacyDeviceManagerBuilder, SerialPortDescriptor};
+
+        let mut bus = dbs_device::device_manager::IoManager::new();
+        let builder = LegacyDeviceManagerBuilder::new()
+            .serial_port(SerialPortDescriptor::new(0x3f8, 4))
+            .serial_port(SerialPortDescriptor::new(0x2f8, 4));
+        let err = builder.build(&mut bus, None).unwrap_err();
+        assert!(matches!(err, Error::IrqOverlap(4)));
+    }
+
+    #[test]
+    #[cfg(target_arch = "aarch64")]
+    fn test_builder_rejects_overlapping_mmio_ranges() {
+        use super::aarch64::{LegacyDeviceManagerBuilder, COM1, COM2};
+        use dbs_device::resources::{DeviceResources, Resource};
+        use std::collections::HashMap;
+
+        let mut com1_resources = DeviceResources::new();
+        com1_resources.append(Resource::MmioAddressRange {
+            base: 0x1c0a_0000,
+            size: 0x200,
+        });
+        com1_resources.append(Resource::LegacyIrq(4));
+        let mut com2_resources = DeviceResources::new();
+        com2_resources.append(Resource::MmioAddressRange {
+            base: 0x1c0a_0000,
+            size: 0x200,
+        });
+        com2_resources.append(Resource::LegacyIrq(5));
+
+        let mut resources = HashMap::new();
+        resources.insert(COM1.to_owned(), com1_resources);
+        resources.insert(COM2.to_owned(), com2_resources);
+
+        let mut bus = dbs_device::device_manager::IoManager::new();
+        let builder = LegacyDeviceManagerBuilder::new()
+            .serial_port(COM1)
+            .serial_port(COM2);
+        let err = builder.build(&mut bus, None, &resources).unwrap_err();
+        assert!(matches!(err, Error::ResourceOverlap(0x1c0a_0000)));
+    }
+
+    #[test]
+    #[cfg(target_arch = "aarch64")]
+    fn test_builder_rejects_colliding_irqs() {
+        use super::aarch64::{LegacyDeviceManagerBuilder, COM1, COM2};
+        use dbs_device::resources::{DeviceResources, Resource};
+        use std::collections::HashMap;
+
+        let mut com1_resources = DeviceResources::new();
+        com1_resources.append(Resource::MmioAddressRange {
+            base: 0x1c0a_0000,
+            size: 0x200,
+        });
+        com1_resources.append(Resource::LegacyIrq(4));
+        let mut com2_resources = DeviceResources::new();
+        com2_resources.append(Resource::MmioAddressRange {
+            base: 0x1c0b_0000,
+            size: 0x200,
+        });
+        com2_resources.append(Resource::LegacyIrq(4));
+
+        let mut resources = HashMap::new();
+        resources.insert(COM1.to_owned(), com1_resources);
+        resources.insert(COM2.to_owned(), com2_resources);
+
+        let mut bus = dbs_device::device_manager::IoManager::new();
+        let builder = LegacyDeviceManagerBuilder::new()
+            .serial_port(COM1)
+            .serial_port(COM2);
+        let err = builder.build(&mut bus, None, &resources).unwrap_err();
+        assert!(matches!(err, Error::IrqOverlap(4)));
+    }
+
+    #[test]
+    #[cfg(target_arch = "aarch64")]
+    fn test_builder_reports_missing_resource_key() {
+        use super::aarch64::{LegacyDeviceManagerBuilder, COM1};
+        use dbs_device::resources::DeviceResources;
+        use std::collections::HashMap;
+
+        let resources: HashMap<String, DeviceResources> = HashMap::new();
+        let mut bus = dbs_device::device_manager::IoManager::new();
+        let builder = LegacyDeviceManagerBuilder::new().serial_port(COM1);
+        let err = builder.build(&mut bus, None, &resources).unwrap_err();
+        assert!(matches!(err, Error::MissingResource(key) if key == COM1));
+    }
 }